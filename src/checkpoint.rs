@@ -0,0 +1,117 @@
+//! On-disk cache for the genesis..height kernel-excess sum.
+//!
+//! The block-walk path in `kernel_excesses_from_blocks` re-derives this sum
+//! from scratch on every run unless it has somewhere to resume from. A
+//! `Checkpoint` is that somewhere: the running commitment and kernel count as
+//! of a specific height and hash, written to `chain_path/.supply_verifier_cache`
+//! so the next invocation only has to walk the blocks mined since, not the
+//! whole chain. The hash is what makes a stale checkpoint detectable - if the
+//! chain has reorged past the checkpointed height, `load` still returns it,
+//! but the caller compares the hash and falls back to a full walk.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use mwc_util::secp::pedersen::Commitment;
+use mwc_util::{from_hex, to_hex};
+
+const CHECKPOINT_FILE_NAME: &str = ".supply_verifier_cache";
+
+/// Sum of all kernel excesses from genesis up to (and including) `height`,
+/// keyed to that block's hash so a stale or forked checkpoint can be
+/// detected and discarded.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Checkpoint {
+    pub height: u64,
+    pub hash: String,
+    pub kernel_count: u64,
+    pub excess_commit: String,
+}
+
+impl Checkpoint {
+    pub fn new(height: u64, hash: String, kernel_count: u64, excess_commit: Commitment) -> Self {
+        Checkpoint {
+            height,
+            hash,
+            kernel_count,
+            excess_commit: to_hex(&excess_commit.0),
+        }
+    }
+
+    pub fn excess_commitment(&self) -> Result<Commitment> {
+        let bytes = from_hex(&self.excess_commit)?;
+        anyhow::ensure!(bytes.len() == 33, "malformed checkpoint commitment");
+        let mut arr = [0u8; 33];
+        arr.copy_from_slice(&bytes);
+        Ok(Commitment(arr))
+    }
+
+    fn file_path(chain_path: &str) -> PathBuf {
+        Path::new(chain_path).join(CHECKPOINT_FILE_NAME)
+    }
+
+    /// Load the checkpoint file if present. A missing or unparsable file is
+    /// treated as "no checkpoint" rather than an error - we always have the
+    /// fallback of walking from genesis.
+    pub fn load(chain_path: &str) -> Option<Checkpoint> {
+        let data = fs::read_to_string(Self::file_path(chain_path)).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    pub fn save(&self, chain_path: &str) -> Result<()> {
+        let data = serde_json::to_string_pretty(self)?;
+        fs::write(Self::file_path(chain_path), data)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tmp_chain_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("supply_verifier_checkpoint_test_{}", name));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn save_then_load_round_trips() {
+        let dir = tmp_chain_dir("round_trip");
+        let chain_path = dir.to_str().unwrap();
+        let commit = Commitment([7u8; 33]);
+
+        let cp = Checkpoint::new(12_345, "deadbeef".to_string(), 9_876, commit);
+        cp.save(chain_path).unwrap();
+
+        let loaded = Checkpoint::load(chain_path).expect("checkpoint should load");
+        assert_eq!(loaded.height, 12_345);
+        assert_eq!(loaded.hash, "deadbeef");
+        assert_eq!(loaded.kernel_count, 9_876);
+        assert_eq!(loaded.excess_commitment().unwrap().0, commit.0);
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn load_missing_file_is_none() {
+        let dir = tmp_chain_dir("missing");
+        let loaded = Checkpoint::load(dir.to_str().unwrap());
+        assert!(loaded.is_none());
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn excess_commitment_rejects_wrong_length() {
+        let cp = Checkpoint {
+            height: 1,
+            hash: "abc".to_string(),
+            kernel_count: 1,
+            excess_commit: to_hex(&[1u8, 2, 3]),
+        };
+        assert!(cp.excess_commitment().is_err());
+    }
+}
@@ -12,9 +12,17 @@
 
 use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use rayon::prelude::*;
+use serde::Serialize;
+
+mod checkpoint;
+mod metrics;
+use checkpoint::Checkpoint;
+use metrics::Metrics;
 
 use mwc_chain::store::ChainStore;
 use mwc_chain::txhashset::TxHashSet;
@@ -23,10 +31,31 @@ use mwc_core::core::hash::Hashed;
 use mwc_core::core::pmmr::ReadablePMMR;
 use mwc_core::global::{self, ChainTypes};
 use mwc_util::secp::key::SecretKey;
-use mwc_util::secp::pedersen::Commitment;
+use mwc_util::secp::pedersen::{Commitment, RangeProof};
 use mwc_util::secp::{ContextFlag, Secp256k1};
 use mwc_util::to_hex;
 
+/// Number of (commitment, proof) pairs verified per `verify_bullet_proof_multi` batch.
+const RANGEPROOF_BATCH_SIZE: usize = 512;
+
+/// How often (in blocks) the kernel-excess checkpoint is rewritten while walking.
+const CHECKPOINT_INTERVAL: u64 = 100_000;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// Where Step 2 reads kernel excesses from. The kernel set is never pruned in
+/// MWC, so `Pmmr` works even against pruned/horizon-synced nodes and is far
+/// cheaper than deserializing every full block.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum KernelSource {
+    Pmmr,
+    Blocks,
+}
+
 #[derive(Parser, Debug)]
 #[command(name = "mwc-supply-verifier")]
 #[command(about = "Cryptographically verify MWC supply integrity")]
@@ -35,6 +64,79 @@ struct Args {
     /// Path to MWC chain data directory
     #[arg(long, default_value = "~/.mwc/main/chain_data")]
     chain_path: String,
+
+    /// Output format: human-readable text or machine-readable JSON
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Also verify each UTXO's bulletproof range proof (slow: checks every
+    /// output commits to a value in [0, 2^64), not just that the balance
+    /// equation holds)
+    #[arg(long)]
+    verify_rangeproofs: bool,
+
+    /// Verify the supply equation at a historical height instead of the tip
+    #[arg(long)]
+    at_height: Option<u64>,
+
+    /// Audit every height in the range `A..B`, reporting the first height
+    /// where the supply equation fails
+    #[arg(long, value_name = "A..B")]
+    scan_range: Option<String>,
+
+    /// Force where kernel excesses are read from. Default auto-detects: try
+    /// the kernel PMMR, fall back to walking block bodies if unavailable.
+    #[arg(long, value_enum)]
+    kernel_source: Option<KernelSource>,
+
+    /// Keep running, polling for new blocks and re-verifying supply whenever
+    /// the tip advances
+    #[arg(long)]
+    watch: bool,
+
+    /// Poll interval in seconds for --watch
+    #[arg(long, default_value_t = 30)]
+    watch_interval_secs: u64,
+
+    /// Expose a Prometheus metrics endpoint (e.g. 127.0.0.1:9898) while
+    /// running in --watch mode
+    #[arg(long, value_name = "HOST:PORT")]
+    metrics_addr: Option<String>,
+}
+
+fn parse_scan_range(s: &str) -> Result<(u64, u64)> {
+    let (a, b) = s
+        .split_once("..")
+        .ok_or_else(|| anyhow::anyhow!("--scan-range expects the form A..B, e.g. 100..200"))?;
+    let a: u64 = a.trim().parse()?;
+    let b: u64 = b.trim().parse()?;
+    anyhow::ensure!(
+        a <= b,
+        "--scan-range start must not exceed end ({} > {})",
+        a,
+        b
+    );
+    Ok((a, b))
+}
+
+/// Machine-readable result of a supply verification run, emitted with `--format json`.
+#[derive(Serialize, Debug)]
+pub(crate) struct SupplyReport {
+    pub(crate) tip_height: u64,
+    pub(crate) utxo_count: usize,
+    pub(crate) kernel_count: usize,
+    lhs_commit: String,
+    rhs_commit: String,
+    total_reward: u64,
+    total_reward_mwc: f64,
+    pub(crate) valid: bool,
+    mismatch_detail: Option<String>,
+    /// Number of range proofs that verified; `None` (`null` in JSON) unless
+    /// `--verify-rangeproofs` was passed.
+    rangeproofs_checked: Option<usize>,
+    /// `None` (`null` in JSON) unless `--verify-rangeproofs` was passed. Any failures also
+    /// mark the report invalid, even if the supply equation itself balances.
+    rangeproofs_failed: Option<usize>,
 }
 
 fn expand_tilde(path: &str) -> String {
@@ -50,16 +152,444 @@ fn main() -> Result<()> {
     let args = Args::parse();
     let chain_path = expand_tilde(&args.chain_path);
 
-    println!("MWC Supply Verifier");
-    println!("===================");
-    println!();
+    anyhow::ensure!(
+        !(args.watch && args.at_height.is_some()),
+        "--watch re-verifies the live tip on every poll and can't be combined with --at-height; \
+         drop one of the two flags"
+    );
+    anyhow::ensure!(
+        !(args.metrics_addr.is_some() && !args.watch),
+        "--metrics-addr only serves metrics while --watch is running; pass --watch too"
+    );
+
+    if let Some(range) = &args.scan_range {
+        let (start, end) = parse_scan_range(range)?;
+        return scan_range(
+            &chain_path,
+            start,
+            end,
+            args.verify_rangeproofs,
+            args.kernel_source,
+            args.format,
+        );
+    }
+
+    if args.watch {
+        return run_watch(
+            &chain_path,
+            args.format,
+            args.verify_rangeproofs,
+            args.kernel_source,
+            Duration::from_secs(args.watch_interval_secs),
+            args.metrics_addr.as_deref(),
+        );
+    }
+
+    if args.format == OutputFormat::Text {
+        println!("MWC Supply Verifier");
+        println!("===================");
+        println!();
+    }
+
+    let report = verify_supply(
+        &chain_path,
+        args.format,
+        args.verify_rangeproofs,
+        args.at_height,
+        args.kernel_source,
+    )?;
+
+    if args.format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    }
 
-    verify_supply(&chain_path)?;
+    if !report.valid {
+        anyhow::bail!(
+            "SUPPLY MISMATCH DETECTED!\n{}",
+            report
+                .mismatch_detail
+                .unwrap_or_else(|| "no detail available".to_string())
+        );
+    }
 
     Ok(())
 }
 
-fn verify_supply(chain_path: &str) -> Result<()> {
+/// Machine-readable summary of a `--scan-range` run, emitted with `--format json`.
+#[derive(Serialize, Debug)]
+struct ScanRangeReport {
+    start_height: u64,
+    end_height: u64,
+    heights_checked: u64,
+    valid: bool,
+    mismatch_height: Option<u64>,
+    mismatch_detail: Option<String>,
+}
+
+/// Check the supply equation at every height in `[start, end]`, stopping and
+/// reporting the first height where it fails. Since the balance equation
+/// must hold at every block, the first failing height is exactly the block
+/// that would have introduced inflation. With `verify_rangeproofs`, a height
+/// whose UTXO set holds an invalid range proof also counts as a failure.
+fn scan_range(
+    chain_path: &str,
+    start: u64,
+    end: u64,
+    verify_rangeproofs: bool,
+    kernel_source: Option<KernelSource>,
+    format: OutputFormat,
+) -> Result<()> {
+    macro_rules! logln {
+        ($($arg:tt)*) => {
+            if format == OutputFormat::Text {
+                println!($($arg)*);
+            }
+        };
+    }
+
+    logln!(
+        "Scanning heights {}..{} for the first supply mismatch...",
+        start,
+        end
+    );
+
+    for height in start..=end {
+        let report = verify_supply(
+            chain_path,
+            OutputFormat::Json,
+            verify_rangeproofs,
+            Some(height),
+            kernel_source,
+        )?;
+
+        if !report.valid {
+            if format == OutputFormat::Json {
+                let summary = ScanRangeReport {
+                    start_height: start,
+                    end_height: end,
+                    heights_checked: height - start + 1,
+                    valid: false,
+                    mismatch_height: Some(height),
+                    mismatch_detail: report.mismatch_detail.clone(),
+                };
+                println!("{}", serde_json::to_string_pretty(&summary)?);
+            } else {
+                println!();
+                println!("FIRST MISMATCH at height {}:", height);
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            }
+            anyhow::bail!(
+                "supply verification first fails at height {} - this block introduced inflation",
+                height
+            );
+        }
+
+        if height % 1_000 == 0 {
+            logln!("  height {} OK", height);
+        }
+    }
+
+    if format == OutputFormat::Json {
+        let summary = ScanRangeReport {
+            start_height: start,
+            end_height: end,
+            heights_checked: end - start + 1,
+            valid: true,
+            mismatch_height: None,
+            mismatch_detail: None,
+        };
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+    } else {
+        println!("No mismatch found in heights {}..{}", start, end);
+    }
+    Ok(())
+}
+
+/// Poll the chain head on an interval and re-verify supply whenever the tip
+/// advances, turning the one-shot CLI into a long-running supply monitor
+/// suitable for running alongside a node. Note this still re-verifies the
+/// full UTXO set on every re-verification, and only benefits from the
+/// kernel-excess checkpoint when Step 2 walks block bodies (`kernel_source`
+/// forced to `Blocks`, or the fallback when the kernel PMMR is unavailable) -
+/// the PMMR path used by default doesn't consult it.
+fn run_watch(
+    chain_path: &str,
+    format: OutputFormat,
+    verify_rangeproofs: bool,
+    kernel_source: Option<KernelSource>,
+    poll_interval: Duration,
+    metrics_addr: Option<&str>,
+) -> Result<()> {
+    let metrics = match metrics_addr {
+        Some(addr) => {
+            let m = Arc::new(Metrics::new()?);
+            m.clone().serve(addr)?;
+            println!("Serving Prometheus metrics on http://{}/metrics", addr);
+            Some(m)
+        }
+        None => None,
+    };
+
+    println!(
+        "Watch mode: polling {} every {:?} for new blocks",
+        chain_path, poll_interval
+    );
+
+    let mut last_height: Option<u64> = None;
+    loop {
+        let head: Result<_> = (|| {
+            let store = ChainStore::new(chain_path)?;
+            let header = store.head_header()?;
+            Ok(header)
+        })();
+        match head {
+            Ok(header) => {
+                if last_height != Some(header.height) {
+                    println!("Tip advanced to height {} - re-verifying...", header.height);
+                    let start = Instant::now();
+                    match verify_supply(chain_path, format, verify_rangeproofs, None, kernel_source)
+                    {
+                        Ok(report) => {
+                            let elapsed = start.elapsed();
+                            if report.valid {
+                                println!(
+                                    "  height={} valid={} utxos={} kernels={} duration={:.2}s",
+                                    report.tip_height,
+                                    report.valid,
+                                    report.utxo_count,
+                                    report.kernel_count,
+                                    elapsed.as_secs_f64()
+                                );
+                            } else {
+                                eprintln!(
+                                    "  ALERT: SUPPLY MISMATCH at height {}: {}",
+                                    report.tip_height,
+                                    report
+                                        .mismatch_detail
+                                        .as_deref()
+                                        .unwrap_or("no detail available")
+                                );
+                            }
+                            if let Some(m) = &metrics {
+                                m.record(&report, elapsed);
+                            }
+                            last_height = Some(report.tip_height);
+                        }
+                        Err(e) => eprintln!("  verification error: {}", e),
+                    }
+                }
+            }
+            Err(e) => eprintln!("  could not read chain head: {}", e),
+        }
+
+        std::thread::sleep(poll_interval);
+    }
+}
+
+/// Sum kernel excesses directly from the kernel MMR at `header`. The kernel
+/// set is never pruned, so this works even on horizon-synced nodes that have
+/// discarded historical block bodies, and it's far cheaper than deserializing
+/// every full block since it's a single indexed pass over the MMR.
+fn kernel_excesses_from_pmmr(
+    txhashset: &TxHashSet,
+    header: &mwc_core::core::BlockHeader,
+    secp: &Secp256k1,
+) -> Result<(Commitment, usize)> {
+    let kernel_pmmr = txhashset.kernel_pmmr_at(header);
+    let mut commits: Vec<Commitment> = Vec::new();
+    for pos in kernel_pmmr.leaf_pos_iter() {
+        let kernel = kernel_pmmr
+            .get_data(pos)
+            .ok_or_else(|| anyhow::anyhow!("missing kernel data at pmmr position {}", pos))?;
+        commits.push(Commitment(kernel.excess.0));
+    }
+    let count = commits.len();
+    let commit = secp.commit_sum(commits, vec![])?;
+    Ok((commit, count))
+}
+
+/// Sum kernel excesses by walking block bodies from `pinned` back to genesis
+/// (or to a cached checkpoint, if one applies). Requires full block bodies
+/// to still be present, so it fails against pruned/horizon-synced nodes -
+/// prefer [`kernel_excesses_from_pmmr`] when available.
+///
+/// The header chain is discovered walking backwards (header-only, cheap),
+/// then kernels are collected replaying that chain forwards from the cached
+/// base (or genesis) towards `pinned`, so the running sum at any point
+/// genuinely covers `[genesis, header.height]` inclusive. The checkpoint is
+/// written immediately every `CHECKPOINT_INTERVAL` blocks during that
+/// forward replay - not deferred until the whole walk finishes - so a crash
+/// partway through a long walk still leaves a correct, usable checkpoint on
+/// disk instead of losing all progress.
+#[allow(clippy::too_many_arguments)]
+fn kernel_excesses_from_blocks(
+    chain_path: &str,
+    store: &Arc<ChainStore>,
+    pinned: &mwc_core::core::BlockHeader,
+    tip_height: u64,
+    secp: &Secp256k1,
+    use_checkpoint: bool,
+    format: OutputFormat,
+) -> Result<(Commitment, usize)> {
+    macro_rules! logln {
+        ($($arg:tt)*) => {
+            if format == OutputFormat::Text {
+                println!($($arg)*);
+            }
+        };
+    }
+
+    let cached = if use_checkpoint {
+        Checkpoint::load(chain_path).and_then(|cp| {
+            if cp.height > tip_height {
+                logln!(
+                    "  Ignoring checkpoint at height {} - newer than tip {}",
+                    cp.height,
+                    tip_height
+                );
+                return None;
+            }
+            Some(cp)
+        })
+    } else {
+        None
+    };
+
+    if let Some(cp) = &cached {
+        logln!(
+            "  (resuming kernel walk from checkpoint at height {})",
+            cp.height
+        );
+    } else {
+        logln!("  (walking block bodies back to genesis)");
+    }
+
+    // Phase 1: discover the header chain from `pinned` back to the cached
+    // checkpoint (or genesis), following `get_previous_header` only - no
+    // block bodies are fetched yet, so this is cheap even for a long walk.
+    let mut headers_desc: Vec<mwc_core::core::BlockHeader> = Vec::new();
+    let mut walk = pinned.clone();
+    let mut reached_checkpoint = false;
+    loop {
+        if let Some(cp) = &cached {
+            if walk.height == cp.height {
+                if walk.hash().to_hex() == cp.hash {
+                    reached_checkpoint = true;
+                    break;
+                }
+                logln!(
+                    "  Checkpoint hash mismatch at height {} - chain reorged past it, \
+                     falling back to a full walk to genesis",
+                    cp.height
+                );
+            }
+        }
+
+        headers_desc.push(walk.clone());
+
+        if walk.height == 0 {
+            break;
+        }
+
+        walk = store.get_previous_header(&walk).map_err(|e| {
+            anyhow::anyhow!(
+                "Header not found at height {} - node is still syncing: {}",
+                walk.height - 1,
+                e
+            )
+        })?;
+    }
+
+    // Phase 2: replay that chain forwards, fetching each block body and
+    // folding its kernel excesses into the running genesis..height sum.
+    let mut running_commit = reached_checkpoint
+        .then(|| cached.as_ref().unwrap().excess_commitment())
+        .transpose()?;
+    let mut running_kernels: u64 = if reached_checkpoint {
+        cached.as_ref().unwrap().kernel_count
+    } else {
+        0
+    };
+    let mut new_kernels: u64 = 0;
+
+    for header in headers_desc.iter().rev() {
+        let block = store.get_block(&header.hash()).map_err(|e| {
+            anyhow::anyhow!(
+                "Block not found at height {} - node is still syncing: {}",
+                header.height,
+                e
+            )
+        })?;
+
+        let mut block_commits: Vec<Commitment> = Vec::new();
+        for k in block.kernels() {
+            block_commits.push(Commitment(k.excess.0));
+        }
+        new_kernels += block_commits.len() as u64;
+        running_kernels += block_commits.len() as u64;
+
+        running_commit = Some(match running_commit {
+            Some(prev) => {
+                block_commits.push(prev);
+                secp.commit_sum(block_commits, vec![])?
+            }
+            None => secp.commit_sum(block_commits, vec![])?,
+        });
+
+        if use_checkpoint && header.height > 0 && header.height % CHECKPOINT_INTERVAL == 0 {
+            logln!("  ... checkpointing at height {}", header.height);
+            let cp = Checkpoint::new(
+                header.height,
+                header.hash().to_hex(),
+                running_kernels,
+                running_commit.expect("just computed above"),
+            );
+            if let Err(e) = cp.save(chain_path) {
+                logln!(
+                    "  Warning: failed to write checkpoint at height {}: {}",
+                    header.height,
+                    e
+                );
+            }
+        }
+    }
+
+    logln!("  Collected {} new kernel excesses", new_kernels);
+
+    let sum_excesses_commit = running_commit
+        .expect("headers_desc always has at least one header unless already at the checkpoint");
+    let collected_kernels = running_kernels as usize;
+
+    if use_checkpoint {
+        let tip_checkpoint = Checkpoint::new(
+            tip_height,
+            pinned.hash().to_hex(),
+            running_kernels,
+            sum_excesses_commit,
+        );
+        if let Err(e) = tip_checkpoint.save(chain_path) {
+            logln!("  Warning: failed to write checkpoint at tip: {}", e);
+        }
+    }
+
+    Ok((sum_excesses_commit, collected_kernels))
+}
+
+fn verify_supply(
+    chain_path: &str,
+    format: OutputFormat,
+    verify_rangeproofs: bool,
+    at_height: Option<u64>,
+    kernel_source: Option<KernelSource>,
+) -> Result<SupplyReport> {
+    macro_rules! logln {
+        ($($arg:tt)*) => {
+            if format == OutputFormat::Text {
+                println!($($arg)*);
+            }
+        };
+    }
+
     // Initialize MWC globals for mainnet
     global::set_local_chain_type(ChainTypes::Mainnet);
     let secp = Secp256k1::with_caps(ContextFlag::Commit);
@@ -75,87 +605,146 @@ fn verify_supply(chain_path: &str) -> Result<()> {
         );
     }
 
-    println!("Opening chain store at: {}", chain_path);
+    logln!("Opening chain store at: {}", chain_path);
 
-    // Open chain store and get the current tip
+    // Open chain store and pin the header to verify against - either the
+    // current tip, or a historical height if `--at-height` was given.
     let store = Arc::new(ChainStore::new(chain_path)?);
-    let pinned = store.head_header().map_err(|e| {
-        anyhow::anyhow!(
-            "Could not read chain head: {}\n\
-             The node may still be syncing. Supply verification requires a fully synced node.",
-            e
-        )
-    })?;
+    let pinned = match at_height {
+        Some(h) => store
+            .get_header_by_height(h)
+            .map_err(|e| anyhow::anyhow!("Could not read header at height {}: {}", h, e))?,
+        None => store.head_header().map_err(|e| {
+            anyhow::anyhow!(
+                "Could not read chain head: {}\n\
+                 The node may still be syncing. Supply verification requires a fully synced node.",
+                e
+            )
+        })?,
+    };
+
+    // Checkpointing assumes we're extending towards the real chain tip; a
+    // historical --at-height query uses a plain walk to genesis instead.
+    let use_checkpoint = at_height.is_none();
 
     let tip_height = pinned.height;
-    println!("Pinned tip height: {}", tip_height);
-    println!();
+    logln!("Verifying supply at height: {}", tip_height);
+    logln!();
 
     // ============ Step 1: Sum all UTXO commitments (LHS) ============
-    println!("Step 1: Collecting UTXO commitments...");
+    logln!("Step 1: Collecting UTXO commitments...");
 
     let txhashset = TxHashSet::open(chain_path.to_string(), store.clone(), None, &secp)?;
     let output_pmmr = txhashset.output_pmmr_at(&pinned);
 
     let mut utxo_commits: Vec<Commitment> = Vec::new();
+    let mut utxo_positions: Vec<u64> = Vec::new();
     let mut utxo_count: usize = 0;
 
     for pos in output_pmmr.leaf_pos_iter() {
         if let Some(output_id) = output_pmmr.get_data(pos) {
             let commit = output_id.commitment();
             utxo_commits.push(Commitment(commit.0));
+            utxo_positions.push(pos);
             utxo_count += 1;
         }
     }
 
-    println!("  Collected {} UTXOs at height {}", utxo_count, tip_height);
+    logln!("  Collected {} UTXOs at height {}", utxo_count, tip_height);
 
-    let lhs_commit = secp.commit_sum(utxo_commits, vec![])?;
+    let lhs_commit = secp.commit_sum(utxo_commits.clone(), vec![])?;
 
-    // ============ Step 2: Sum all kernel excesses ============
-    println!("Step 2: Collecting kernel excesses (walking chain to genesis)...");
-
-    let mut kernel_excess_commits: Vec<Commitment> = Vec::new();
-    let mut collected_kernels: usize = 0;
+    // ============ Step 1.5: Verify range proofs (optional) ============
+    let (rangeproofs_checked, rangeproofs_failed) = if verify_rangeproofs {
+        logln!("Step 1.5: Verifying range proofs for every UTXO...");
 
-    let mut walk = pinned.clone();
-    loop {
-        let block = store.get_block(&walk.hash()).map_err(|e| {
-            anyhow::anyhow!(
-                "Block not found at height {} - node is still syncing: {}",
-                walk.height,
-                e
-            )
-        })?;
-
-        for k in block.kernels() {
-            kernel_excess_commits.push(Commitment(k.excess.0));
-            collected_kernels += 1;
+        let rproof_pmmr = txhashset.rproof_pmmr_at(&pinned);
+        let mut pairs: Vec<(Commitment, RangeProof)> = Vec::with_capacity(utxo_commits.len());
+        for (commit, pos) in utxo_commits.iter().zip(utxo_positions.iter()) {
+            let proof = rproof_pmmr
+                .get_data(*pos)
+                .ok_or_else(|| anyhow::anyhow!("missing range proof at pmmr position {}", pos))?;
+            pairs.push((*commit, proof));
         }
 
-        if walk.height % 100_000 == 0 && walk.height > 0 {
-            println!("  ... at height {}", walk.height);
+        // A failed batch doesn't abort the run - fall back to checking each
+        // proof in that batch individually so one bad proof doesn't prevent
+        // us from reporting how many others in the batch were fine, and
+        // carry the (verified, failed) tally forward into the report.
+        let (verified, failed): (usize, usize) = pairs
+            .par_chunks(RANGEPROOF_BATCH_SIZE)
+            .map(|batch| -> (usize, usize) {
+                let commits: Vec<Commitment> = batch.iter().map(|(c, _)| *c).collect();
+                let proofs: Vec<RangeProof> = batch.iter().map(|(_, p)| p.clone()).collect();
+                if secp
+                    .verify_bullet_proof_multi(commits, proofs, None)
+                    .is_ok()
+                {
+                    return (batch.len(), 0);
+                }
+
+                let failed_in_batch = batch
+                    .iter()
+                    .filter(|(commit, proof)| {
+                        secp.verify_bullet_proof_multi(vec![*commit], vec![proof.clone()], None)
+                            .is_err()
+                    })
+                    .count();
+                (batch.len() - failed_in_batch, failed_in_batch)
+            })
+            .reduce(|| (0, 0), |a, b| (a.0 + b.0, a.1 + b.1));
+
+        if failed > 0 {
+            logln!("  Verified {} range proofs, {} FAILED", verified, failed);
+        } else {
+            logln!("  Verified {} range proofs (all valid)", verified);
         }
+        (Some(verified), Some(failed))
+    } else {
+        (None, None)
+    };
 
-        if walk.height == 0 {
-            break;
+    // ============ Step 2: Sum all kernel excesses ============
+    let try_pmmr = !matches!(kernel_source, Some(KernelSource::Blocks));
+    let mut pmmr_result: Option<(Commitment, usize)> = None;
+
+    if try_pmmr {
+        logln!("Step 2: Collecting kernel excesses from the kernel PMMR...");
+        match kernel_excesses_from_pmmr(&txhashset, &pinned, &secp) {
+            Ok(result) => pmmr_result = Some(result),
+            Err(e) => {
+                if matches!(kernel_source, Some(KernelSource::Pmmr)) {
+                    return Err(e.context(
+                        "--kernel-source=pmmr was forced but the kernel PMMR is unavailable",
+                    ));
+                }
+                logln!(
+                    "  Kernel PMMR unavailable ({}), falling back to walking block bodies",
+                    e
+                );
+            }
         }
-
-        walk = store.get_previous_header(&walk).map_err(|e| {
-            anyhow::anyhow!(
-                "Header not found at height {} - node is still syncing: {}",
-                walk.height - 1,
-                e
-            )
-        })?;
     }
 
-    println!("  Collected {} kernel excesses", collected_kernels);
-
-    let sum_excesses_commit = secp.commit_sum(kernel_excess_commits, vec![])?;
+    let (sum_excesses_commit, collected_kernels) = if let Some((commit, count)) = pmmr_result {
+        logln!("  Collected {} kernel excesses from the kernel PMMR", count);
+        (commit, count)
+    } else {
+        kernel_excesses_from_blocks(
+            chain_path,
+            &store,
+            &pinned,
+            tip_height,
+            &secp,
+            use_checkpoint,
+            format,
+        )?
+    };
+
+    logln!("  Total kernel excesses: {}", collected_kernels);
 
     // ============ Step 3: Compute offset and reward commitments ============
-    println!("Step 3: Computing offset and reward commitments...");
+    logln!("Step 3: Computing offset and reward commitments...");
 
     let offset_bf = pinned.total_kernel_offset;
     let offset_sk = SecretKey::from_slice(&secp, offset_bf.as_ref())?;
@@ -163,45 +752,112 @@ fn verify_supply(chain_path: &str) -> Result<()> {
 
     let total_reward = calc_mwc_block_overage(tip_height, true);
     let reward_mwc = total_reward as f64 / MWC_BASE as f64;
-    println!(
+    logln!(
         "  Total reward at height {}: {:.9} MWC",
-        tip_height, reward_mwc
+        tip_height,
+        reward_mwc
     );
 
     let reward_commit = secp.commit_value(total_reward)?;
 
     // ============ Step 4: Compute RHS and compare ============
-    println!("Step 4: Verifying supply equation...");
-    println!();
+    logln!("Step 4: Verifying supply equation...");
+    logln!();
 
     let rhs_commit = secp.commit_sum(
         vec![sum_excesses_commit, offset_commit, reward_commit],
         vec![],
     )?;
 
-    println!("Supply Equation:");
-    println!("  ΣUTXO == Σkernels + offset·G + reward·H");
-    println!();
-    println!("  LHS (ΣUTXO):          {}", to_hex(&lhs_commit.0));
-    println!("  RHS (Σkern+off+rew):  {}", to_hex(&rhs_commit.0));
-    println!();
-
-    if lhs_commit == rhs_commit {
-        println!("RESULT: MWC supply is valid!");
-        println!();
-        println!("This cryptographically proves that no MWC were created out of thin air.");
-        println!("Every coin in existence is backed by either:");
-        println!("  - A valid transaction kernel, or");
-        println!("  - The coinbase block reward");
-        Ok(())
-    } else {
-        anyhow::bail!(
-            "SUPPLY MISMATCH DETECTED!\n\
-             LHS: {}\n\
-             RHS: {}\n\
-             This should never happen on a valid chain.",
+    logln!("Supply Equation:");
+    logln!("  ΣUTXO == Σkernels + offset·G + reward·H");
+    logln!();
+    logln!("  LHS (ΣUTXO):          {}", to_hex(&lhs_commit.0));
+    logln!("  RHS (Σkern+off+rew):  {}", to_hex(&rhs_commit.0));
+    logln!();
+
+    let equation_valid = lhs_commit == rhs_commit;
+    let valid = equation_valid && rangeproofs_failed.unwrap_or(0) == 0;
+    let mismatch_detail = if valid {
+        None
+    } else if !equation_valid {
+        Some(format!(
+            "LHS: {}\nRHS: {}\nThis should never happen on a valid chain.",
             to_hex(&lhs_commit.0),
             to_hex(&rhs_commit.0)
-        )
+        ))
+    } else {
+        Some(format!(
+            "{} of {} range proofs failed verification.",
+            rangeproofs_failed.unwrap_or(0),
+            rangeproofs_checked.unwrap_or(0) + rangeproofs_failed.unwrap_or(0)
+        ))
+    };
+
+    if format == OutputFormat::Text {
+        if valid {
+            println!("RESULT: MWC supply is valid!");
+            if let Some(checked) = rangeproofs_checked {
+                println!("  ({} range proofs verified)", checked);
+            }
+            println!();
+            println!("This cryptographically proves that no MWC were created out of thin air.");
+            println!("Every coin in existence is backed by either:");
+            println!("  - A valid transaction kernel, or");
+            println!("  - The coinbase block reward");
+        } else {
+            println!("RESULT: SUPPLY MISMATCH DETECTED!");
+        }
+    }
+
+    Ok(SupplyReport {
+        tip_height,
+        utxo_count,
+        kernel_count: collected_kernels,
+        lhs_commit: to_hex(&lhs_commit.0),
+        rhs_commit: to_hex(&rhs_commit.0),
+        total_reward,
+        total_reward_mwc: reward_mwc,
+        valid,
+        mismatch_detail,
+        rangeproofs_checked,
+        rangeproofs_failed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_scan_range_accepts_well_formed_input() {
+        assert_eq!(parse_scan_range("100..200").unwrap(), (100, 200));
+        assert_eq!(parse_scan_range(" 100 .. 200 ").unwrap(), (100, 200));
+        assert_eq!(parse_scan_range("0..0").unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn parse_scan_range_rejects_inverted_bounds() {
+        assert!(parse_scan_range("200..100").is_err());
+    }
+
+    #[test]
+    fn parse_scan_range_rejects_garbage() {
+        assert!(parse_scan_range("not-a-range").is_err());
+        assert!(parse_scan_range("100-200").is_err());
+        assert!(parse_scan_range("abc..200").is_err());
+        assert!(parse_scan_range("100..xyz").is_err());
+        assert!(parse_scan_range("").is_err());
+    }
+
+    #[test]
+    fn expand_tilde_only_rewrites_leading_home_prefix() {
+        std::env::set_var("HOME", "/home/tester");
+        assert_eq!(expand_tilde("~/chain_data"), "/home/tester/chain_data");
+        assert_eq!(expand_tilde("/absolute/chain_data"), "/absolute/chain_data");
+        assert_eq!(
+            expand_tilde("relative/~/chain_data"),
+            "relative/~/chain_data"
+        );
     }
 }
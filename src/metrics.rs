@@ -0,0 +1,98 @@
+//! Prometheus metrics endpoint for `--watch` mode.
+//!
+//! `run_watch` calls `Metrics::record` after each re-verification; `serve`
+//! binds an HTTP listener and renders the registry on every request to
+//! `/metrics`, so the verifier can be added to a scrape config instead of
+//! parsing its stdout.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use prometheus::{Encoder, Gauge, IntGauge, Registry, TextEncoder};
+
+use crate::SupplyReport;
+
+pub struct Metrics {
+    registry: Registry,
+    last_verified_height: IntGauge,
+    last_verification_outcome: IntGauge,
+    last_utxo_count: IntGauge,
+    last_kernel_count: IntGauge,
+    last_verification_duration_seconds: Gauge,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new();
+
+        let last_verified_height = IntGauge::new(
+            "mwc_supply_verifier_last_verified_height",
+            "Height of the most recently verified block",
+        )?;
+        let last_verification_outcome = IntGauge::new(
+            "mwc_supply_verifier_last_outcome",
+            "1 if the last verification passed, 0 if it failed",
+        )?;
+        let last_utxo_count = IntGauge::new(
+            "mwc_supply_verifier_last_utxo_count",
+            "Number of UTXOs seen in the last verification",
+        )?;
+        let last_kernel_count = IntGauge::new(
+            "mwc_supply_verifier_last_kernel_count",
+            "Number of kernels seen in the last verification",
+        )?;
+        let last_verification_duration_seconds = Gauge::new(
+            "mwc_supply_verifier_last_duration_seconds",
+            "Wall-clock duration of the last verification pass",
+        )?;
+
+        registry.register(Box::new(last_verified_height.clone()))?;
+        registry.register(Box::new(last_verification_outcome.clone()))?;
+        registry.register(Box::new(last_utxo_count.clone()))?;
+        registry.register(Box::new(last_kernel_count.clone()))?;
+        registry.register(Box::new(last_verification_duration_seconds.clone()))?;
+
+        Ok(Metrics {
+            registry,
+            last_verified_height,
+            last_verification_outcome,
+            last_utxo_count,
+            last_kernel_count,
+            last_verification_duration_seconds,
+        })
+    }
+
+    pub fn record(&self, report: &SupplyReport, duration: Duration) {
+        self.last_verified_height.set(report.tip_height as i64);
+        self.last_verification_outcome
+            .set(if report.valid { 1 } else { 0 });
+        self.last_utxo_count.set(report.utxo_count as i64);
+        self.last_kernel_count.set(report.kernel_count as i64);
+        self.last_verification_duration_seconds
+            .set(duration.as_secs_f64());
+    }
+
+    fn render(&self) -> Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Serve `/metrics` on `addr` from a background thread. Returns once the
+    /// listener is bound; the thread runs for the lifetime of the process.
+    pub fn serve(self: Arc<Self>, addr: &str) -> Result<()> {
+        let server = tiny_http::Server::http(addr)
+            .map_err(|e| anyhow::anyhow!("failed to bind metrics endpoint on {}: {}", addr, e))?;
+
+        std::thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let body = self.render().unwrap_or_default();
+                let response = tiny_http::Response::from_data(body);
+                let _ = request.respond(response);
+            }
+        });
+
+        Ok(())
+    }
+}